@@ -1,4 +1,3 @@
-use std::{iter::Peekable, str::Chars};
 use crate::{error::Result,error::Error};
 
 
@@ -30,6 +29,15 @@ impl Keyword {
     }
 }
 
+// 数字字面量的进制，决定 `value` 中数字部分应如何解析
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Radix {
+    Bin,
+    Oct,
+    Dec,
+    Hex,
+}
+
 #[derive(Debug,Clone,PartialEq)]
 pub enum Token {
     // 关键字
@@ -37,7 +45,8 @@ pub enum Token {
     // 其他类型的字符串，比如实体名
     Ident(String),
     String(String),
-    Number(String),
+    // 数字字面量，radix 为 Dec 以外时 is_float 恒为 false（非十进制没有小数/指数形式）
+    Number { radix: Radix, is_float: bool, value: String },
     OpenParen,
     CloseParen,
     Comma,
@@ -46,40 +55,172 @@ pub enum Token {
     Plus,
     Minus,
     Slash,
+    Period,
+    Percent,
+    Caret,
+    Exclamation,
+    Question,
+    Equal,
+    NotEqual,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    // 无法识别的字符，扫描会记录一条错误后继续前进，而不是中断整个 token 流
+    Unknown(char),
+}
+
+// 词法单元在源文本中的位置，行列号均从 1 开始计数
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Position {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Position {
+    fn start() -> Self {
+        Self { line: 1, col: 1 }
+    }
+}
+
+// 词法单元覆盖的源文本范围，[start, end)
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+// 带位置信息的 Token，供后续报错和解析时定位使用
+#[derive(Debug,Clone,PartialEq)]
+pub struct Spanned<T> {
+    pub span: Span,
+    pub token: T,
+}
+
+// 对输入字符序列的游标，保留已消费字符的历史，从而支持任意距离的
+// 向前窥视（`peek_nth`），弥补 `Peekable<Chars>` 只能看一个字符的局限
+struct Cursor {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Cursor {
+    fn new(input: &str) -> Self {
+        Self { chars: input.chars().collect(), pos: 0 }
+    }
+
+    // 窥视从当前位置起第 n 个字符（n = 0 即下一个将被消费的字符），不移动游标
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.chars.get(self.pos + n).copied()
+    }
+
+    // 消费并返回下一个字符，移动游标
+    fn consume(&mut self) -> Option<char> {
+        let c = self.peek_nth(0)?;
+        self.pos += 1;
+        Some(c)
+    }
 }
 
-pub struct Lexer<'a> {
-    iter: Peekable<Chars<'a>>
-} 
+pub struct Lexer {
+    cursor: Cursor,
+    pos: Position,
+    // 扫描过程中遇到的非致命错误（如无法识别的字符），按出现顺序累积
+    errors: Vec<(Span,Error)>,
+}
 
 // 自定义迭代器
-impl<'a> Iterator for Lexer<'a> {
-    type Item = Result<Token>;
+impl Iterator for Lexer {
+    type Item = Result<Spanned<Token>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.scan() {
-            Ok(Some(token)) => Some(Ok(token)),
-            Ok(None) => self.iter.peek().map(|c|Err(Error::Parse(format!("[Lexer] Unexpected character {}",c)))),
+            Ok(Some(spanned)) => Some(Ok(spanned)),
+            Ok(None) => None,
             Err(err) => Some(Err(err)),
         }
     }
 }
 
-impl<'a> Lexer<'a> {
-    pub fn new(sql_text: &'a str) -> Self {
+impl Lexer {
+    pub fn new(sql_text: &str) -> Self {
         Self {
-            iter: sql_text.chars().peekable(),
+            cursor: Cursor::new(sql_text),
+            pos: Position::start(),
+            errors: Vec::new(),
+        }
+    }
+
+    // 扫描过程中累积的非致命错误，调用方可以在消费完 token 流后再统一检查
+    pub fn errors(&self) -> &[(Span,Error)] {
+        &self.errors
+    }
+
+    // 构造一条带位置信息的词法错误，让 Error::Parse 的消息能指向源文本中的具体位置
+    fn err_at(&self, pos: Position, message: impl std::fmt::Display) -> Error {
+        Error::Parse(format!("[Lexer] {} at {}:{}",message,pos.line,pos.col))
+    }
+
+    // 消费一个字符，并同步更新行列号
+    fn advance(&mut self) -> Option<char> {
+        let c = self.cursor.consume()?;
+        if c == '\n' {
+            self.pos.line += 1;
+            self.pos.col = 1;
+        } else {
+            self.pos.col += 1;
         }
+        Some(c)
     }
 
     fn erase_whitespace(&mut self) {
         self.next_while(|c| c.is_whitespace());
     }
 
+    // 跳过一个注释（如果当前位置是注释的话），返回是否跳过了注释
+    fn skip_comment(&mut self) -> Result<bool> {
+        let first = match self.cursor.peek_nth(0) {
+            Some(c) => c,
+            None => return Ok(false),
+        };
+        match first {
+            '-' if self.cursor.peek_nth(1) == Some('-') => {
+                self.advance();
+                self.advance();
+                self.next_while(|c| c != '\n');
+                Ok(true)
+            }
+            '/' if self.cursor.peek_nth(1) == Some('*') => {
+                self.advance();
+                self.advance();
+                let mut depth = 1;
+                loop {
+                    match self.advance() {
+                        Some('*') if self.cursor.peek_nth(0) == Some('/') => {
+                            self.advance();
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        Some('/') if self.cursor.peek_nth(0) == Some('*') => {
+                            self.advance();
+                            depth += 1;
+                        }
+                        Some(_) => {}
+                        None => return Err(self.err_at(self.pos,"Unterminated block comment")),
+                    }
+                }
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
     // 如果满足条件，则指向下一个字符，并返回该字符
     fn next_if<F: Fn(char)->bool> (&mut self,predicate:F) -> Option<char> {
-        self.iter.peek().filter(|&c| predicate(*c))?; // 返回None  
-        self.iter.next()  // 非 None , 指向下一个字符,并返回当前字符
+        self.cursor.peek_nth(0).filter(|&c| predicate(c))?; // 返回None
+        self.advance()  // 非 None , 指向下一个字符,并返回当前字符
     }
 
     fn next_while<F: Fn(char)->bool>(&mut self, predicate:F) -> Option<String> {
@@ -90,57 +231,198 @@ impl<'a> Lexer<'a> {
         Some(value).filter(|v|!v.is_empty())
     }
 
-    // 只有是 Token 类型才跳转到下一个，并返回 Token 
+    // 只有是 Token 类型才跳转到下一个，并返回 Token
     fn next_if_token<F:Fn(char)->Option<Token>>(&mut self,predicate:F) -> Option<Token> {
-        let token = self.iter.peek().and_then(|c|predicate(*c))?;
-        self.iter.next();
+        let token = self.cursor.peek_nth(0).and_then(predicate)?;
+        self.advance();
         Some(token)
     }
 
-    // 获取下一个 token 
-    fn scan(&mut self) -> Result<Option<Token>> {
-        // 消除字符串中空白字符 
-        self.erase_whitespace();
-        // 根据第一个字符判断
-        match self.iter.peek() {
-            Some('\'') => self.scan_string(), // 扫描字符串
-            Some(c) if c.is_ascii_digit() => Ok(self.scan_number()),
-            Some(c) if c.is_alphabetic() => Ok(self.scan_ident()),
-            Some(_) => Ok(self.scan_symbol()),
-            None => Ok(None),
+    // 获取下一个 token ，并记录该 token 在源文本中的起止位置
+    fn scan(&mut self) -> Result<Option<Spanned<Token>>> {
+        // 空白和注释可能交替出现，需要循环清除直到两者都不再匹配
+        loop {
+            self.erase_whitespace();
+            if !self.skip_comment()? {
+                break;
+            }
         }
+        let start = self.pos;
+        let peeked = match self.cursor.peek_nth(0) {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+        // 根据第一个字符判断
+        let token = match peeked {
+            '\'' => self.scan_string()?, // 扫描字符串
+            '"' => self.scan_quoted_ident()?, // 扫描双引号标识符
+            c if c.is_ascii_digit() => self.scan_number()?,
+            c if c.is_alphabetic() => self.scan_ident(),
+            _ => self.scan_symbol(),
+        };
+        let token = match token {
+            Some(token) => token,
+            // 无法识别的符号：记一条错误并前进一个字符，而不是让整个
+            // token 流在第一个坏字符处中断
+            None => {
+                let c = self.advance().expect("peeked char must exist");
+                let span = Span { start, end: self.pos };
+                let err = self.err_at(start,format!("Unexpected character {}",c));
+                self.errors.push((span,err));
+                Token::Unknown(c)
+            }
+        };
+        Ok(Some(Spanned { span: Span { start, end: self.pos }, token }))
     }
 
-    // 扫描字符串
+    // 扫描字符串，支持 `''` 转义单引号以及常见的反斜杠转义序列
     fn scan_string(&mut self) -> Result<Option<Token>> {
         // 判断是否以单引号开头
         if self.next_if(|c|c=='\'').is_none() {
             return Ok(None);
         }
-        
+
         let mut val = String::new();
         loop {
-            match self.iter.next() {
+            match self.advance() {
+                // 连续两个单引号是标准 SQL 中单引号的转义写法
+                Some('\'') if self.next_if(|c| c == '\'').is_some() => val.push('\''),
                 Some('\'') => break,
+                Some('\\') => val.push(self.scan_string_escape()?),
                 Some(c) => val.push(c),
-                None => return Err(Error::Parse(format!("[Lexer] Unexpected end of string"))),
+                None => return Err(self.err_at(self.pos,"Unexpected end of string")),
             }
         }
-        
+
         Ok(Some(Token::String(val)))
     }
 
-    // 扫描数字
-    fn scan_number(&mut self) -> Option<Token> {
-        let mut num = self.next_while(|c|c.is_ascii_digit())?;
+    // 扫描反斜杠转义序列，返回其代表的字符
+    fn scan_string_escape(&mut self) -> Result<char> {
+        match self.advance() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some('\\') => Ok('\\'),
+            Some('\'') => Ok('\''),
+            Some('x') => {
+                let mut hex = String::new();
+                for _ in 0..2 {
+                    match self.advance() {
+                        Some(c) if c.is_ascii_hexdigit() => hex.push(c),
+                        _ => return Err(self.err_at(self.pos,"Invalid \\x escape sequence")),
+                    }
+                }
+                let code = u8::from_str_radix(&hex,16).map_err(|_|self.err_at(self.pos,format!("Invalid \\x escape sequence {}",hex)))?;
+                Ok(code as char)
+            }
+            Some('u') => self.scan_unicode_escape(),
+            Some(c) => Err(self.err_at(self.pos,format!("Unknown escape sequence \\{}",c))),
+            None => Err(self.err_at(self.pos,"Unexpected end of string")),
+        }
+    }
+
+    // 扫描 `\u{...}` 或 `\uHHHH` 形式的 Unicode 转义
+    fn scan_unicode_escape(&mut self) -> Result<char> {
+        let hex = if self.next_if(|c| c == '{').is_some() {
+            let hex = self.next_while(|c| c.is_ascii_hexdigit()).ok_or_else(||self.err_at(self.pos,"Empty \\u{} escape"))?;
+            if self.next_if(|c| c == '}').is_none() {
+                return Err(self.err_at(self.pos,"Unterminated \\u{} escape"));
+            }
+            hex
+        } else {
+            let mut hex = String::new();
+            for _ in 0..4 {
+                match self.advance() {
+                    Some(c) if c.is_ascii_hexdigit() => hex.push(c),
+                    _ => return Err(self.err_at(self.pos,"Invalid \\u escape sequence")),
+                }
+            }
+            hex
+        };
+        let code = u32::from_str_radix(&hex,16).map_err(|_|self.err_at(self.pos,format!("Invalid \\u escape sequence {}",hex)))?;
+        char::from_u32(code).ok_or_else(||self.err_at(self.pos,format!("Invalid unicode code point \\u{{{}}}",hex)))
+    }
+
+    // 扫描双引号括起的标识符，保留大小写，不作关键字识别
+    fn scan_quoted_ident(&mut self) -> Result<Option<Token>> {
+        if self.next_if(|c|c=='"').is_none() {
+            return Ok(None);
+        }
+
+        let mut val = String::new();
+        loop {
+            match self.advance() {
+                Some('"') => break,
+                Some(c) => val.push(c),
+                None => return Err(self.err_at(self.pos,"Unexpected end of quoted identifier")),
+            }
+        }
+
+        Ok(Some(Token::Ident(val)))
+    }
+
+    // 判断当前位置是否是 `0` 开头的非十进制前缀（0x/0o/0b）
+    // 注意：单独的 `0` 必须仍按十进制扫描，`0.5` 不能被误认成带前缀的 `0`
+    fn peek_radix_prefix(&self) -> Option<Radix> {
+        if self.cursor.peek_nth(0) != Some('0') {
+            return None;
+        }
+        match self.cursor.peek_nth(1) {
+            Some('x') | Some('X') => Some(Radix::Hex),
+            Some('o') | Some('O') => Some(Radix::Oct),
+            Some('b') | Some('B') => Some(Radix::Bin),
+            _ => None,
+        }
+    }
+
+    fn is_radix_digit(radix: Radix, c: char) -> bool {
+        match radix {
+            Radix::Bin => c == '0' || c == '1',
+            Radix::Oct => ('0'..='7').contains(&c),
+            Radix::Dec => c.is_ascii_digit(),
+            Radix::Hex => c.is_ascii_hexdigit(),
+        }
+    }
+
+    // 扫描数字，支持十进制小数/科学计数法，以及 0x/0o/0b 非十进制前缀
+    fn scan_number(&mut self) -> Result<Option<Token>> {
+        if let Some(radix) = self.peek_radix_prefix() {
+            self.advance();
+            self.advance();
+            let value = self.next_while(|c|Self::is_radix_digit(radix,c))
+                .ok_or_else(||self.err_at(self.pos,"Expected digits after radix prefix"))?;
+            return Ok(Some(Token::Number { radix, is_float: false, value }));
+        }
+
+        let mut value = match self.next_while(|c|c.is_ascii_digit()) {
+            Some(num) => num,
+            None => return Ok(None),
+        };
+
+        let mut is_float = false;
         if let Some(sep) = self.next_if(|c|c=='.') {
-            num.push(sep);
+            is_float = true;
+            value.push(sep);
             // 扫描小数点之后的部分
             while let Some(c) = self.next_if(|c|c.is_ascii_digit()) {
-                num.push(c);
+                value.push(c);
+            }
+        }
+
+        // 科学计数法后缀，如 1e10 / 1.5E-3
+        if let Some(e) = self.next_if(|c|c=='e'||c=='E') {
+            is_float = true;
+            value.push(e);
+            if let Some(sign) = self.next_if(|c|c=='+'||c=='-') {
+                value.push(sign);
             }
+            let exponent = self.next_while(|c|c.is_ascii_digit())
+                .ok_or_else(||self.err_at(self.pos,"Expected digits in exponent"))?;
+            value.push_str(&exponent);
         }
-        Some(Token::Number(num))
+
+        Ok(Some(Token::Number { radix: Radix::Dec, is_float, value }))
     }
 
     // 扫描 Ident ，如表名，列名, 也可能是关键字，比如 true / false 
@@ -154,7 +436,9 @@ impl<'a> Lexer<'a> {
     }
 
     fn scan_symbol(&mut self) -> Option<Token> {
-        self.next_if_token(|c| match c {
+        // 先按单字符匹配出一个初步的 Token，再根据后面一个字符决定是否要
+        // 把它升级成双字符的关系运算符（<= / >= / <> / !=）
+        let token = self.next_if_token(|c| match c {
             '*' => Some(Token::Asterisk),
             '(' => Some(Token::OpenParen),
             ')' => Some(Token::CloseParen),
@@ -163,7 +447,23 @@ impl<'a> Lexer<'a> {
             '+' => Some(Token::Plus),
             '-' => Some(Token::Minus),
             '/' => Some(Token::Slash),
+            '.' => Some(Token::Period),
+            '%' => Some(Token::Percent),
+            '^' => Some(Token::Caret),
+            '?' => Some(Token::Question),
+            '=' => Some(Token::Equal),
+            '<' => Some(Token::LessThan),
+            '>' => Some(Token::GreaterThan),
+            '!' => Some(Token::Exclamation),
             _ => None,
+        })?;
+
+        Some(match token {
+            Token::LessThan if self.next_if(|c| c == '=').is_some() => Token::LessThanOrEqual,
+            Token::LessThan if self.next_if(|c| c == '>').is_some() => Token::NotEqual,
+            Token::GreaterThan if self.next_if(|c| c == '=').is_some() => Token::GreaterThanOrEqual,
+            Token::Exclamation if self.next_if(|c| c == '=').is_some() => Token::NotEqual,
+            other => other,
         })
     }
 }
@@ -177,6 +477,7 @@ mod tests {
     #[test]
     fn test_lexer_create_table() -> Result<()> {
         let tokens = Lexer::new("create table tbl (a int primary key , b integer);").peekable().collect::<Result<Vec<_>>>()?;
+        let tokens: Vec<Token> = tokens.into_iter().map(|spanned| spanned.token).collect();
         println!("{:?}",tokens);
 
         assert_eq!(tokens,vec![
@@ -196,4 +497,122 @@ mod tests {
         ]);
         Ok(())
     }
+
+    #[test]
+    fn test_lexer_operators() -> Result<()> {
+        let tokens = Lexer::new("= != <> < <= > >= . % ^ ! ?").peekable().collect::<Result<Vec<_>>>()?;
+        let tokens: Vec<Token> = tokens.into_iter().map(|spanned| spanned.token).collect();
+        println!("{:?}",tokens);
+
+        assert_eq!(tokens,vec![
+            Token::Equal,
+            Token::NotEqual,
+            Token::NotEqual,
+            Token::LessThan,
+            Token::LessThanOrEqual,
+            Token::GreaterThan,
+            Token::GreaterThanOrEqual,
+            Token::Period,
+            Token::Percent,
+            Token::Caret,
+            Token::Exclamation,
+            Token::Question,
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lexer_spans() -> Result<()> {
+        use super::{Position,Span};
+
+        let spanned = Lexer::new("select\na").peekable().collect::<Result<Vec<_>>>()?;
+        println!("{:?}",spanned);
+
+        assert_eq!(spanned[0].span,Span { start: Position{line:1,col:1}, end: Position{line:1,col:7} });
+        assert_eq!(spanned[1].span,Span { start: Position{line:2,col:1}, end: Position{line:2,col:2} });
+        Ok(())
+    }
+
+    #[test]
+    fn test_lexer_comments() -> Result<()> {
+        let tokens = Lexer::new("select -- trailing comment\n/* a /* nested */ block */a").peekable().collect::<Result<Vec<_>>>()?;
+        let tokens: Vec<Token> = tokens.into_iter().map(|spanned| spanned.token).collect();
+        println!("{:?}",tokens);
+
+        assert_eq!(tokens,vec![
+            Token::Ident("select".to_string()),
+            Token::Ident("a".to_string()),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lexer_unterminated_block_comment() {
+        let result = Lexer::new("/* never closed").peekable().collect::<Result<Vec<_>>>();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_lexer_string_escapes() -> Result<()> {
+        let tokens = Lexer::new(r#"'it''s' '\n\t\\' '\x41' '\u{1F600}'"#).peekable().collect::<Result<Vec<_>>>()?;
+        let tokens: Vec<Token> = tokens.into_iter().map(|spanned| spanned.token).collect();
+        println!("{:?}",tokens);
+
+        assert_eq!(tokens,vec![
+            Token::String("it's".to_string()),
+            Token::String("\n\t\\".to_string()),
+            Token::String("A".to_string()),
+            Token::String("\u{1F600}".to_string()),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lexer_quoted_ident() -> Result<()> {
+        let tokens = Lexer::new(r#""Select" "Table""#).peekable().collect::<Result<Vec<_>>>()?;
+        let tokens: Vec<Token> = tokens.into_iter().map(|spanned| spanned.token).collect();
+        println!("{:?}",tokens);
+
+        assert_eq!(tokens,vec![
+            Token::Ident("Select".to_string()),
+            Token::Ident("Table".to_string()),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lexer_numbers() -> Result<()> {
+        use super::Radix;
+
+        let tokens = Lexer::new("0 0.5 1e10 1.5E-3 0x1F 0o17 0b101").peekable().collect::<Result<Vec<_>>>()?;
+        let tokens: Vec<Token> = tokens.into_iter().map(|spanned| spanned.token).collect();
+        println!("{:?}",tokens);
+
+        assert_eq!(tokens,vec![
+            Token::Number { radix: Radix::Dec, is_float: false, value: "0".to_string() },
+            Token::Number { radix: Radix::Dec, is_float: true, value: "0.5".to_string() },
+            Token::Number { radix: Radix::Dec, is_float: true, value: "1e10".to_string() },
+            Token::Number { radix: Radix::Dec, is_float: true, value: "1.5E-3".to_string() },
+            Token::Number { radix: Radix::Hex, is_float: false, value: "1F".to_string() },
+            Token::Number { radix: Radix::Oct, is_float: false, value: "17".to_string() },
+            Token::Number { radix: Radix::Bin, is_float: false, value: "101".to_string() },
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lexer_recovers_from_unknown_characters() -> Result<()> {
+        let mut lexer = Lexer::new("select @ from");
+        let tokens = (&mut lexer).collect::<Result<Vec<_>>>()?;
+        let tokens: Vec<Token> = tokens.into_iter().map(|spanned| spanned.token).collect();
+        println!("{:?}",tokens);
+
+        assert_eq!(tokens,vec![
+            Token::Ident("select".to_string()),
+            Token::Unknown('@'),
+            Token::Ident("from".to_string()),
+        ]);
+        assert_eq!(lexer.errors().len(),1);
+        Ok(())
+    }
 }
\ No newline at end of file